@@ -35,6 +35,9 @@
 
 #![deny(unsafe_code)]
 
+pub mod pinyin;
+pub mod trie;
+
 use std::option::Option;
 
 /// Used to represent a range of characters in a string.
@@ -50,7 +53,7 @@ pub struct DictEntry<T> {
     definitions: Slice,
 }
 
-impl std::fmt::Debug for DictEntry<String> {
+impl<T: AsRef<str>> std::fmt::Debug for DictEntry<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         write!(
             f,
@@ -91,103 +94,440 @@ impl<T: AsRef<str>> DictEntry<T> {
         let line = line.trim_matches('/');
         line.split('/')
     }
+
+    /// Like `definitions()`, but recognizes the cross-reference and
+    /// classifier patterns CC-CEDICT embeds in glosses (`variant of
+    /// X[pinyin]`, `see X[pinyin]`, `see also X[pinyin]`, `old variant of
+    /// X[pinyin]` and `CL:X[pinyin]`), so callers can link between entries
+    /// instead of treating every gloss as opaque text.
+    ///
+    /// # Examples
+    /// ```
+    /// use cedict::Definition;
+    ///
+    /// let line = "旧金山 旧金山 [Jiu4 jin1 shan1] /San Francisco/CL:個[ge4]/";
+    /// let entry = cedict::parse_dict_entry(line).unwrap();
+    /// let definitions: Vec<_> = entry.parsed_definitions().collect();
+    ///
+    /// assert_eq!(definitions[0], Definition::Text("San Francisco"));
+    /// assert_eq!(definitions[1], Definition::Classifier { headword: "個", pinyin: "ge4" });
+    /// ```
+    pub fn parsed_definitions<'a>(&'a self) -> impl Iterator<Item = Definition<'a>> {
+        self.definitions().map(parse_definition)
+    }
+
+    /// Renders the entry back into a canonical CC-CEDICT line.
+    ///
+    /// # Examples
+    /// ```
+    /// let line = "你好 你好 [ni3 hao3] /Hello!/Hi!/";
+    /// let parsed = cedict::parse_dict_entry(line).unwrap();
+    ///
+    /// assert_eq!(parsed.to_line(), line);
+    /// ```
+    pub fn to_line(&self) -> String {
+        self.to_string()
+    }
 }
 
-pub fn parse_dict_entry<T: AsRef<str>>(line: T) -> Option<DictEntry<T>> {
+impl<T: AsRef<str>> std::fmt::Display for DictEntry<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {} [{}] /", self.traditional(), self.simplified(), self.pinyin())?;
+        for definition in self.definitions() {
+            write!(f, "{}/", definition)?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason `DictEntryBuilder::build()` refused to assemble an entry.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DictEntryBuilderError {
+    /// The traditional headword was empty.
+    EmptyTraditional,
+    /// The simplified headword was empty.
+    EmptySimplified,
+    /// The pinyin reading was empty.
+    EmptyPinyin,
+    /// No definitions were pushed.
+    NoDefinitions,
+    /// The traditional headword contained whitespace, which would be
+    /// mistaken for the field separator on re-parse.
+    TraditionalContainsWhitespace,
+    /// The simplified headword contained whitespace, which would be
+    /// mistaken for the field separator on re-parse.
+    SimplifiedContainsWhitespace,
+    /// The pinyin reading contained a `[` or `]`, which would be mistaken
+    /// for the start or end of the pinyin field on re-parse.
+    PinyinContainsBracket,
+    /// The pinyin reading contained a control character such as `\n` or
+    /// `\r`, which would corrupt the line on write-out.
+    PinyinContainsControlCharacter,
+    /// A definition contained a `/`, which would corrupt the `/`-delimited
+    /// definitions field on round-trip.
+    DefinitionContainsSlash(String),
+    /// A definition contained a control character such as `\n` or `\r`,
+    /// which would corrupt the line on write-out.
+    DefinitionContainsControlCharacter(String),
+}
+
+impl std::fmt::Display for DictEntryBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DictEntryBuilderError::EmptyTraditional => write!(f, "traditional headword is empty"),
+            DictEntryBuilderError::EmptySimplified => write!(f, "simplified headword is empty"),
+            DictEntryBuilderError::EmptyPinyin => write!(f, "pinyin reading is empty"),
+            DictEntryBuilderError::NoDefinitions => write!(f, "no definitions were given"),
+            DictEntryBuilderError::TraditionalContainsWhitespace => {
+                write!(f, "traditional headword contains whitespace")
+            }
+            DictEntryBuilderError::SimplifiedContainsWhitespace => {
+                write!(f, "simplified headword contains whitespace")
+            }
+            DictEntryBuilderError::PinyinContainsBracket => {
+                write!(f, "pinyin reading contains a '[' or ']'")
+            }
+            DictEntryBuilderError::PinyinContainsControlCharacter => {
+                write!(f, "pinyin reading contains a control character")
+            }
+            DictEntryBuilderError::DefinitionContainsSlash(definition) => {
+                write!(f, "definition `{}` contains a '/'", definition)
+            }
+            DictEntryBuilderError::DefinitionContainsControlCharacter(definition) => {
+                write!(f, "definition `{}` contains a control character", definition)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DictEntryBuilderError {}
+
+/// Builds a `DictEntry` from its parts, producing the canonical
+/// `traditional simplified [pinyin] /definition1/definition2/` line.
+///
+/// # Examples
+/// ```
+/// use cedict::DictEntryBuilder;
+///
+/// let entry = DictEntryBuilder::new()
+///     .traditional("你好")
+///     .simplified("你好")
+///     .pinyin("ni3 hao3")
+///     .push_definition("Hello!")
+///     .push_definition("Hi!")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(entry.to_line(), "你好 你好 [ni3 hao3] /Hello!/Hi!/");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DictEntryBuilder {
+    traditional: String,
+    simplified: String,
+    pinyin: String,
+    definitions: Vec<String>,
+}
+
+impl DictEntryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn traditional(mut self, traditional: impl Into<String>) -> Self {
+        self.traditional = traditional.into();
+        self
+    }
+
+    pub fn simplified(mut self, simplified: impl Into<String>) -> Self {
+        self.simplified = simplified.into();
+        self
+    }
+
+    pub fn pinyin(mut self, pinyin: impl Into<String>) -> Self {
+        self.pinyin = pinyin.into();
+        self
+    }
+
+    pub fn push_definition(mut self, definition: impl Into<String>) -> Self {
+        self.definitions.push(definition.into());
+        self
+    }
+
+    /// Validates the parts given so far and builds the canonical line,
+    /// parsing it back into a `DictEntry` so the result is guaranteed to
+    /// round-trip through `parse_dict_entry`.
+    pub fn build(self) -> Result<DictEntry<String>, DictEntryBuilderError> {
+        if self.traditional.is_empty() {
+            return Err(DictEntryBuilderError::EmptyTraditional);
+        }
+        if self.simplified.is_empty() {
+            return Err(DictEntryBuilderError::EmptySimplified);
+        }
+        if self.pinyin.is_empty() {
+            return Err(DictEntryBuilderError::EmptyPinyin);
+        }
+        if self.definitions.is_empty() {
+            return Err(DictEntryBuilderError::NoDefinitions);
+        }
+        if self.traditional.chars().any(char::is_whitespace) {
+            return Err(DictEntryBuilderError::TraditionalContainsWhitespace);
+        }
+        if self.simplified.chars().any(char::is_whitespace) {
+            return Err(DictEntryBuilderError::SimplifiedContainsWhitespace);
+        }
+        if self.pinyin.contains('[') || self.pinyin.contains(']') {
+            return Err(DictEntryBuilderError::PinyinContainsBracket);
+        }
+        if self.pinyin.chars().any(|c| c.is_control()) {
+            return Err(DictEntryBuilderError::PinyinContainsControlCharacter);
+        }
+        if let Some(definition) = self.definitions.iter().find(|d| d.contains('/')) {
+            return Err(DictEntryBuilderError::DefinitionContainsSlash(
+                definition.clone(),
+            ));
+        }
+        if let Some(definition) = self
+            .definitions
+            .iter()
+            .find(|d| d.chars().any(|c| c.is_control()))
+        {
+            return Err(DictEntryBuilderError::DefinitionContainsControlCharacter(
+                definition.clone(),
+            ));
+        }
+
+        let line = format!(
+            "{} {} [{}] /{}/",
+            self.traditional,
+            self.simplified,
+            self.pinyin,
+            self.definitions.join("/")
+        );
+
+        Ok(parse_dict_entry(line).expect("DictEntryBuilder always produces a valid CC-CEDICT line"))
+    }
+}
+
+/// A single `/`-delimited gloss from a `DictEntry`'s definitions, with
+/// CC-CEDICT's cross-reference and classifier conventions recognized.
+///
+/// Returned by `DictEntry::parsed_definitions()`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Definition<'a> {
+    /// A plain-text gloss with no recognized structure.
+    Text(&'a str),
+    /// A `variant of`/`see`/`see also`/`old variant of` cross-reference to
+    /// another headword.
+    Reference {
+        kind: &'a str,
+        headword: &'a str,
+        pinyin: &'a str,
+    },
+    /// A `CL:` measure-word classifier.
+    Classifier { headword: &'a str, pinyin: &'a str },
+}
+
+/// Relation keywords recognized by `parse_definition`, tried in order;
+/// `see also` comes before the `see` it starts with so it is matched first.
+const REFERENCE_KEYWORDS: &[&str] = &["old variant of", "see also", "see", "variant of"];
+
+fn parse_definition(gloss: &str) -> Definition<'_> {
+    for &kind in REFERENCE_KEYWORDS {
+        if let Some(rest) = gloss.strip_prefix(kind) {
+            if let Some((headword, pinyin, remainder)) = parse_headword_and_pinyin(rest) {
+                // A reference only ever points at a single headword; if
+                // anything unexpected trails it, don't discard that part
+                // of the gloss by pretending we understood the whole thing.
+                if remainder.trim().is_empty() {
+                    return Definition::Reference { kind, headword, pinyin };
+                }
+            }
+        }
+    }
+
+    if let Some(rest) = gloss.strip_prefix("CL:") {
+        if let Some((headword, pinyin, remainder)) = parse_headword_and_pinyin(rest) {
+            // CC-CEDICT sometimes lists several classifiers, e.g.
+            // `CL:本[ben3],冊[ce4]`. We only extract a single one, so fall
+            // back to the raw text rather than silently dropping the rest.
+            if remainder.trim().is_empty() {
+                return Definition::Classifier { headword, pinyin };
+            }
+        }
+    }
+
+    Definition::Text(gloss)
+}
+
+/// Parses a leading `headword[pinyin]` pattern, e.g. ` 上海[Shang4 hai3]`,
+/// returning the headword, the pinyin and whatever text trails it.
+fn parse_headword_and_pinyin(s: &str) -> Option<(&str, &str, &str)> {
+    let bracket_start = s.find('[')?;
+    let bracket_end = bracket_start + s[bracket_start..].find(']')?;
+
+    let headword = s[..bracket_start].trim();
+    if headword.is_empty() {
+        return None;
+    }
+
+    Some((
+        headword,
+        &s[bracket_start + 1..bracket_end],
+        &s[bracket_end + 1..],
+    ))
+}
+
+/// The reason `parse_dict_entry_verbose` failed, together with the byte
+/// offset into the line at which the problem was found.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    /// The line is empty or a `#` comment, so it is not an entry at all.
+    NotAnEntry,
+    /// No space was found to terminate the traditional headword.
+    MissingSpaceAfterTraditional,
+    /// No simplified headword follows the traditional one.
+    MissingSimplified,
+    /// No space was found to terminate the simplified headword.
+    MissingSpaceAfterSimplified,
+    /// Expected a `[` to start the pinyin reading.
+    MissingOpeningBracket,
+    /// No `]` was found to terminate the pinyin reading.
+    UnterminatedPinyin,
+    /// No space was found after the closing `]`.
+    MissingSpaceAfterPinyin,
+    /// No `/`-delimited definitions follow the pinyin reading.
+    MissingDefinitions,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?} at byte offset {}", self.kind, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a single CC-CEDICT entry line, returning the byte offset and
+/// kind of the first problem found instead of silently giving up.
+///
+/// # Examples
+/// ```
+/// use cedict::{parse_dict_entry_verbose, ParseErrorKind};
+///
+/// let err = parse_dict_entry_verbose("你好 你好 ni3 hao3] /Hello!/").unwrap_err();
+/// assert_eq!(err.kind, ParseErrorKind::MissingOpeningBracket);
+/// ```
+pub fn parse_dict_entry_verbose<T: AsRef<str>>(line: T) -> Result<DictEntry<T>, ParseError> {
+    let end_of_line = line.as_ref().len();
     let mut chars = line.as_ref().char_indices().peekable();
 
+    let err = |offset: usize, kind: ParseErrorKind| Err(ParseError { offset, kind });
+
     // Skip comments and empty lines
     match chars.peek() {
-        Some((_, '#')) => return None,
-        None => return None,
+        Some((_, '#')) | None => return err(0, ParseErrorKind::NotAnEntry),
         _ => (),
     }
 
-    let traditional_start = chars.peek()?.0;
+    let traditional_start = chars.peek().unwrap().0;
     loop {
         match chars.peek() {
             Some((_, ' ')) => break,
-            None => return None,
+            None => return err(end_of_line, ParseErrorKind::MissingSpaceAfterTraditional),
             _ => {
                 chars.next();
             }
         }
     }
-    let traditional_end = chars.peek()?.0;
+    let traditional_end = chars.peek().unwrap().0;
 
     // We know the next character is a space, so we can skip it
-    match chars.next() {
-        Some((_, ' ')) => (),
-        _ => return None,
-    };
+    chars.next();
 
-    let simplified_start = chars.next()?.0;
+    let simplified_start = match chars.next() {
+        Some((offset, _)) => offset,
+        None => return err(end_of_line, ParseErrorKind::MissingSimplified),
+    };
     loop {
         match chars.peek() {
             Some((_, ' ')) => break,
-            None => return None,
+            None => return err(end_of_line, ParseErrorKind::MissingSpaceAfterSimplified),
             _ => {
                 chars.next();
             }
         }
     }
-    let simplified_end = chars.peek()?.0;
+    let simplified_end = chars.peek().unwrap().0;
 
     // We know the next character is a space, so we can skip it
-    match chars.next() {
-        Some((_, ' ')) => (),
-        _ => return None,
-    };
+    chars.next();
 
     // Expecting a '['
     match chars.next() {
         Some((_, '[')) => (),
-        _ => return None,
+        Some((offset, _)) => return err(offset, ParseErrorKind::MissingOpeningBracket),
+        None => return err(end_of_line, ParseErrorKind::MissingOpeningBracket),
     };
 
-    let pinyin_start = chars.next()?.0;
+    let pinyin_start = match chars.next() {
+        Some((offset, _)) => offset,
+        None => return err(end_of_line, ParseErrorKind::UnterminatedPinyin),
+    };
     loop {
         match chars.peek() {
             Some((_, ']')) => break,
-            None => return None,
+            None => return err(end_of_line, ParseErrorKind::UnterminatedPinyin),
             _ => {
                 chars.next();
             }
         }
     }
-    let pinyin_end = chars.peek()?.0;
+    let pinyin_end = chars.peek().unwrap().0;
 
     // We know the next character is a ']', so we can skip it
-    match chars.next() {
-        Some((_, ']')) => (),
-        _ => return None,
-    };
+    chars.next();
 
     // We know the next character is a space, so we can skip it
     match chars.next() {
         Some((_, ' ')) => (),
-        _ => return None,
+        Some((offset, _)) => return err(offset, ParseErrorKind::MissingSpaceAfterPinyin),
+        None => return err(end_of_line, ParseErrorKind::MissingSpaceAfterPinyin),
     };
 
     // We know the next character is a '/', so we can skip it
     match chars.next() {
         Some((_, '/')) => (),
-        _ => return None,
+        Some((offset, _)) => return err(offset, ParseErrorKind::MissingDefinitions),
+        None => return err(end_of_line, ParseErrorKind::MissingDefinitions),
     };
 
-    let definitions_start = chars.next()?.0;
-
-    let len = line.as_ref().len();
+    let definitions_start = match chars.next() {
+        Some((offset, _)) => offset,
+        None => return err(end_of_line, ParseErrorKind::MissingDefinitions),
+    };
 
-    Some(DictEntry {
+    Ok(DictEntry {
         line,
         traditional: (traditional_start, traditional_end),
         simplified: (simplified_start, simplified_end),
         pinyin: (pinyin_start, pinyin_end),
-        definitions: (definitions_start, len),
+        definitions: (definitions_start, end_of_line),
     })
 }
 
+/// Parses a single CC-CEDICT entry line. A thin wrapper around
+/// `parse_dict_entry_verbose` for callers that don't need to know why a
+/// line failed to parse.
+pub fn parse_dict_entry<T: AsRef<str>>(line: T) -> Option<DictEntry<T>> {
+    parse_dict_entry_verbose(line).ok()
+}
+
 /// Check if a line is a comment. Comments start with a '#'.
 pub fn is_comment(line: &str) -> bool {
     let bytes = line.as_bytes();
@@ -206,7 +546,7 @@ pub enum Line {
     Metadata(String, String),
     Entry(DictEntry<String>),
     Empty,
-    Incorrect,
+    Incorrect(ParseError),
 }
 
 pub fn parse_line<T: AsRef<str>>(line: T) -> Line {
@@ -228,9 +568,9 @@ pub fn parse_line<T: AsRef<str>>(line: T) -> Line {
         // Strip the '#' prefix
         Line::Comment(line[1..].trim().into())
     } else {
-        match parse_dict_entry(line.into()) {
-            Some(entry) => Line::Entry(entry),
-            None => Line::Incorrect,
+        match parse_dict_entry_verbose(line.into()) {
+            Ok(entry) => Line::Entry(entry),
+            Err(error) => Line::Incorrect(error),
         }
     }
 }
@@ -245,6 +585,62 @@ pub fn parse_reader<T: std::io::Read>(f: T) -> impl Iterator<Item = DictEntry<St
     lines.filter_map(|x| parse_dict_entry(x))
 }
 
+/// Parses an in-memory CC-CEDICT file, yielding entries that borrow
+/// directly from `input` instead of allocating a `String` per line. Ideal
+/// for scanning a memory-mapped file with no per-entry allocation.
+///
+/// # Examples
+/// ```
+/// let contents = "你好 你好 [ni3 hao3] /Hello!/Hi!/\n睡覺 睡觉 [shui4 jiao4] /to sleep/";
+/// let entries: Vec<_> = cedict::parse_str(contents).collect();
+///
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[0].simplified(), "你好");
+/// ```
+pub fn parse_str(input: &str) -> impl Iterator<Item = DictEntry<&str>> {
+    input
+        .split('\n')
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !is_comment(line))
+        .filter_map(parse_dict_entry)
+}
+
+/// Writes a sequence of `Line`s back out in CC-CEDICT format, preserving
+/// `#!` metadata and `#` comment lines so a file can be read, modified and
+/// rewritten without losing anything but malformed entries.
+///
+/// # Examples
+/// ```
+/// let lines = vec![
+///     cedict::parse_line("#! version = 1"),
+///     cedict::parse_line("你好 你好 [ni3 hao3] /Hello!/Hi!/"),
+/// ];
+///
+/// let mut out = Vec::new();
+/// cedict::write_entries(&mut out, lines.into_iter()).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "#! version = 1\n你好 你好 [ni3 hao3] /Hello!/Hi!/\n"
+/// );
+/// ```
+pub fn write_entries<W: std::io::Write>(
+    mut w: W,
+    lines: impl Iterator<Item = Line>,
+) -> std::io::Result<()> {
+    for line in lines {
+        match line {
+            Line::Entry(entry) => writeln!(w, "{}", entry)?,
+            Line::Metadata(key, value) => writeln!(w, "#! {} = {}", key, value)?,
+            Line::Comment(text) => writeln!(w, "# {}", text)?,
+            Line::Empty => writeln!(w)?,
+            Line::Incorrect(_) => continue,
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +655,208 @@ mod tests {
         assert_eq!(entry.definitions().nth(0), Some("to go to bed"));
         assert_eq!(entry.definitions().nth(1), Some("to sleep"));
     }
+
+    #[test]
+    fn test_parse_dict_entry_verbose() {
+        let error = parse_dict_entry_verbose("你好 你好 ni3 hao3] /Hello!/").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::MissingOpeningBracket);
+
+        let error = parse_dict_entry_verbose("你好 你好 [ni3 hao3 /Hello!/").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnterminatedPinyin);
+
+        let error = parse_dict_entry_verbose("你好 你好 [ni3 hao3]").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::MissingSpaceAfterPinyin);
+    }
+
+    #[test]
+    fn test_parsed_definitions() {
+        let line = "旧金山 旧金山 [Jiu4 jin1 shan1] /San Francisco/CL:個[ge4]/variant of 上海[Shang4 hai3]/see 你好[ni3 hao3]/see also 你好[ni3 hao3]/old variant of 你好[ni3 hao3]/";
+        let entry = parse_dict_entry(line).unwrap();
+        let definitions: Vec<_> = entry.parsed_definitions().collect();
+
+        assert_eq!(definitions[0], Definition::Text("San Francisco"));
+        assert_eq!(
+            definitions[1],
+            Definition::Classifier {
+                headword: "個",
+                pinyin: "ge4"
+            }
+        );
+        assert_eq!(
+            definitions[2],
+            Definition::Reference {
+                kind: "variant of",
+                headword: "上海",
+                pinyin: "Shang4 hai3"
+            }
+        );
+        assert_eq!(
+            definitions[3],
+            Definition::Reference {
+                kind: "see",
+                headword: "你好",
+                pinyin: "ni3 hao3"
+            }
+        );
+        assert_eq!(
+            definitions[4],
+            Definition::Reference {
+                kind: "see also",
+                headword: "你好",
+                pinyin: "ni3 hao3"
+            }
+        );
+        assert_eq!(
+            definitions[5],
+            Definition::Reference {
+                kind: "old variant of",
+                headword: "你好",
+                pinyin: "ni3 hao3"
+            }
+        );
+    }
+
+    #[test]
+    fn test_parsed_definitions_multiple_classifiers() {
+        let line = "本 本 [ben3] /book/CL:本[ben3],冊[ce4]/";
+        let entry = parse_dict_entry(line).unwrap();
+        let definitions: Vec<_> = entry.parsed_definitions().collect();
+
+        // Only one classifier can be extracted, so the gloss is returned
+        // as-is rather than silently dropping the second one.
+        assert_eq!(
+            definitions[1],
+            Definition::Text("CL:本[ben3],冊[ce4]")
+        );
+    }
+
+    #[test]
+    fn test_parse_str() {
+        let contents =
+            "你好 你好 [ni3 hao3] /Hello!/Hi!/\n睡覺 睡觉 [shui4 jiao4] /to sleep/\n# a comment\n";
+        let entries: Vec<_> = parse_str(contents).collect();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].simplified(), "你好");
+        assert_eq!(entries[1].simplified(), "睡觉");
+    }
+
+    #[test]
+    fn test_parse_str_crlf() {
+        let contents = "你好 你好 [ni3 hao3] /Hello!/Hi!/\r\n睡覺 睡觉 [shui4 jiao4] /to sleep/\r\n";
+        let entries: Vec<_> = parse_str(contents).collect();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].definitions().collect::<Vec<_>>(),
+            vec!["Hello!", "Hi!"]
+        );
+        assert_eq!(
+            entries[1].definitions().collect::<Vec<_>>(),
+            vec!["to sleep"]
+        );
+    }
+
+    #[test]
+    fn test_to_line_round_trip() {
+        let line = "睡覺 睡觉 [shui4 jiao4] /to go to bed/to sleep/";
+        let entry = parse_dict_entry(line).unwrap();
+        assert_eq!(entry.to_line(), line);
+    }
+
+    #[test]
+    fn test_builder() {
+        let entry = DictEntryBuilder::new()
+            .traditional("你好")
+            .simplified("你好")
+            .pinyin("ni3 hao3")
+            .push_definition("Hello!")
+            .push_definition("Hi!")
+            .build()
+            .unwrap();
+
+        assert_eq!(entry.to_line(), "你好 你好 [ni3 hao3] /Hello!/Hi!/");
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        let result = DictEntryBuilder::new()
+            .simplified("")
+            .pinyin("ni3 hao3")
+            .push_definition("Hello!")
+            .build();
+        assert_eq!(result, Err(DictEntryBuilderError::EmptyTraditional));
+
+        let result = DictEntryBuilder::new()
+            .traditional("你好")
+            .simplified("你好")
+            .pinyin("ni3 hao3")
+            .push_definition("a/b")
+            .build();
+        assert_eq!(
+            result,
+            Err(DictEntryBuilderError::DefinitionContainsSlash("a/b".to_string()))
+        );
+
+        let result = DictEntryBuilder::new()
+            .traditional("a b")
+            .simplified("你好")
+            .pinyin("ni3 hao3")
+            .push_definition("Hello!")
+            .build();
+        assert_eq!(result, Err(DictEntryBuilderError::TraditionalContainsWhitespace));
+
+        let result = DictEntryBuilder::new()
+            .traditional("你好")
+            .simplified("你 好")
+            .pinyin("ni3 hao3")
+            .push_definition("Hello!")
+            .build();
+        assert_eq!(result, Err(DictEntryBuilderError::SimplifiedContainsWhitespace));
+
+        let result = DictEntryBuilder::new()
+            .traditional("你好")
+            .simplified("你好")
+            .pinyin("ni3] hao3")
+            .push_definition("Hello!")
+            .build();
+        assert_eq!(result, Err(DictEntryBuilderError::PinyinContainsBracket));
+
+        let result = DictEntryBuilder::new()
+            .traditional("你好")
+            .simplified("你好")
+            .pinyin("ni3\nhao3")
+            .push_definition("Hello!")
+            .build();
+        assert_eq!(result, Err(DictEntryBuilderError::PinyinContainsControlCharacter));
+
+        let result = DictEntryBuilder::new()
+            .traditional("你好")
+            .simplified("你好")
+            .pinyin("ni3 hao3")
+            .push_definition("evil\nline injection")
+            .build();
+        assert_eq!(
+            result,
+            Err(DictEntryBuilderError::DefinitionContainsControlCharacter(
+                "evil\nline injection".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_write_entries() {
+        let lines = vec![
+            parse_line("#! version = 1"),
+            parse_line("你好 你好 [ni3 hao3] /Hello!/Hi!/"),
+        ];
+
+        let mut out = Vec::new();
+        write_entries(&mut out, lines.into_iter()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "#! version = 1\n你好 你好 [ni3 hao3] /Hello!/Hi!/\n"
+        );
+    }
 }
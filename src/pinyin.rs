@@ -0,0 +1,231 @@
+//! Tokenizing and reformatting CC-CEDICT pinyin, e.g. turning
+//! `"ni3 hao3"` into `"nǐ hǎo"` and back.
+
+/// Tone mark, base letter and tone number for every accented vowel used in
+/// pinyin. Used to place marks when converting to diacritics and to read
+/// them back off when converting to numbered tones.
+const TONE_CHARS: &[(char, char, u8)] = &[
+    ('ā', 'a', 1),
+    ('á', 'a', 2),
+    ('ǎ', 'a', 3),
+    ('à', 'a', 4),
+    ('ē', 'e', 1),
+    ('é', 'e', 2),
+    ('ě', 'e', 3),
+    ('è', 'e', 4),
+    ('ī', 'i', 1),
+    ('í', 'i', 2),
+    ('ǐ', 'i', 3),
+    ('ì', 'i', 4),
+    ('ō', 'o', 1),
+    ('ó', 'o', 2),
+    ('ǒ', 'o', 3),
+    ('ò', 'o', 4),
+    ('ū', 'u', 1),
+    ('ú', 'u', 2),
+    ('ǔ', 'u', 3),
+    ('ù', 'u', 4),
+    ('ǖ', 'ü', 1),
+    ('ǘ', 'ü', 2),
+    ('ǚ', 'ü', 3),
+    ('ǜ', 'ü', 4),
+    ('Ā', 'A', 1),
+    ('Á', 'A', 2),
+    ('Ǎ', 'A', 3),
+    ('À', 'A', 4),
+    ('Ē', 'E', 1),
+    ('É', 'E', 2),
+    ('Ě', 'E', 3),
+    ('È', 'E', 4),
+    ('Ī', 'I', 1),
+    ('Í', 'I', 2),
+    ('Ǐ', 'I', 3),
+    ('Ì', 'I', 4),
+    ('Ō', 'O', 1),
+    ('Ó', 'O', 2),
+    ('Ǒ', 'O', 3),
+    ('Ò', 'O', 4),
+    ('Ū', 'U', 1),
+    ('Ú', 'U', 2),
+    ('Ǔ', 'U', 3),
+    ('Ù', 'U', 4),
+    ('Ǖ', 'Ü', 1),
+    ('Ǘ', 'Ü', 2),
+    ('Ǚ', 'Ü', 3),
+    ('Ǜ', 'Ü', 4),
+];
+
+/// Splits a pinyin string (as returned by `DictEntry::pinyin()`) into its
+/// syllables, each paired with its tone number. A trailing digit 1-5 is
+/// stripped off as the tone; a missing or `5` digit means the neutral
+/// tone.
+///
+/// # Examples
+/// ```
+/// use cedict::pinyin::syllables;
+///
+/// let parsed: Vec<_> = syllables("ni3 hao3").collect();
+/// assert_eq!(parsed, vec![("ni", 3), ("hao", 3)]);
+/// ```
+pub fn syllables(pinyin: &str) -> impl Iterator<Item = (&str, u8)> {
+    pinyin.split_whitespace().map(|syllable| {
+        match syllable.chars().last().and_then(|c| c.to_digit(10)) {
+            Some(tone @ 1..=5) => {
+                let base = &syllable[..syllable.len() - 1];
+                (base, tone as u8)
+            }
+            _ => (syllable, 5),
+        }
+    })
+}
+
+/// Converts `u:` or `v` (CC-CEDICT's ASCII spellings of `ü`) into the
+/// actual character, preserving case.
+fn normalize_u(base: &str) -> String {
+    base.replace("u:", "ü")
+        .replace("U:", "Ü")
+        .chars()
+        .map(|c| match c {
+            'v' => 'ü',
+            'V' => 'Ü',
+            other => other,
+        })
+        .collect()
+}
+
+fn is_ae(c: char) -> bool {
+    matches!(c, 'a' | 'A' | 'e' | 'E')
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'A' | 'e' | 'E' | 'i' | 'I' | 'o' | 'O' | 'u' | 'U' | 'ü' | 'Ü')
+}
+
+/// Finds the index of the vowel that should carry the tone mark, following
+/// the standard pinyin placement rule: `a`/`e` wins outright, then the `o`
+/// in `ou`, then the last vowel in the syllable.
+fn tone_mark_index(chars: &[char]) -> Option<usize> {
+    if let Some(i) = chars.iter().position(|&c| is_ae(c)) {
+        return Some(i);
+    }
+
+    for i in 0..chars.len().saturating_sub(1) {
+        if matches!(chars[i], 'o' | 'O') && matches!(chars[i + 1], 'u' | 'U') {
+            return Some(i);
+        }
+    }
+
+    chars.iter().rposition(|&c| is_vowel(c))
+}
+
+fn mark_char(c: char, tone: u8) -> char {
+    TONE_CHARS
+        .iter()
+        .find(|(_, base, t)| *base == c && *t == tone)
+        .map(|(marked, _, _)| *marked)
+        .unwrap_or(c)
+}
+
+fn unmark_char(c: char) -> (char, Option<u8>) {
+    TONE_CHARS
+        .iter()
+        .find(|(marked, _, _)| *marked == c)
+        .map(|(_, base, tone)| (*base, Some(*tone)))
+        .unwrap_or((c, None))
+}
+
+/// Converts numbered-tone pinyin (`"ni3 hao3"`) into accented Unicode
+/// (`"nǐ hǎo"`).
+///
+/// # Examples
+/// ```
+/// assert_eq!(cedict::pinyin::to_diacritics("ni3 hao3"), "nǐ hǎo");
+/// assert_eq!(cedict::pinyin::to_diacritics("lu:4"), "lǜ");
+/// assert_eq!(cedict::pinyin::to_diacritics("ma"), "ma");
+/// ```
+pub fn to_diacritics(pinyin: &str) -> String {
+    syllables(pinyin)
+        .map(|(base, tone)| {
+            let normalized = normalize_u(base);
+
+            if tone == 5 {
+                return normalized;
+            }
+
+            let chars: Vec<char> = normalized.chars().collect();
+            match tone_mark_index(&chars) {
+                Some(mark_index) => chars
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &c)| if i == mark_index { mark_char(c, tone) } else { c })
+                    .collect(),
+                None => normalized,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Converts accented Unicode pinyin (`"nǐ hǎo"`) back into CC-CEDICT's
+/// numbered-tone form (`"ni3 hao3"`). `ü` is written back out as `u:`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(cedict::pinyin::to_numbered("nǐ hǎo"), "ni3 hao3");
+/// assert_eq!(cedict::pinyin::to_numbered("lǜ"), "lu:4");
+/// ```
+pub fn to_numbered(pinyin: &str) -> String {
+    pinyin
+        .split_whitespace()
+        .map(|syllable| {
+            let mut tone = 5u8;
+            let mut base = String::new();
+
+            for c in syllable.chars() {
+                let (plain, marked_tone) = unmark_char(c);
+                base.push(plain);
+                if let Some(t) = marked_tone {
+                    tone = t;
+                }
+            }
+
+            let base = base.replace('ü', "u:").replace('Ü', "U:");
+            if tone == 5 {
+                base
+            } else {
+                format!("{}{}", base, tone)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syllables() {
+        let parsed: Vec<_> = syllables("ni3 hao3").collect();
+        assert_eq!(parsed, vec![("ni", 3), ("hao", 3)]);
+
+        let parsed: Vec<_> = syllables("ma").collect();
+        assert_eq!(parsed, vec![("ma", 5)]);
+    }
+
+    #[test]
+    fn test_to_diacritics() {
+        assert_eq!(to_diacritics("ni3 hao3"), "nǐ hǎo");
+        assert_eq!(to_diacritics("zhong1 guo2"), "zhōng guó");
+        assert_eq!(to_diacritics("lu:4"), "lǜ");
+        assert_eq!(to_diacritics("nv3"), "nǚ");
+        assert_eq!(to_diacritics("Beijing1"), "Bēijing");
+    }
+
+    #[test]
+    fn test_to_numbered() {
+        assert_eq!(to_numbered("nǐ hǎo"), "ni3 hao3");
+        assert_eq!(to_numbered("zhōng guó"), "zhong1 guo2");
+        assert_eq!(to_numbered("lǜ"), "lu:4");
+    }
+}
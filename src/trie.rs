@@ -0,0 +1,167 @@
+//! An in-memory prefix index over a set of `DictEntry`s, for exact lookup,
+//! autocomplete and greedy Chinese text segmentation.
+
+use crate::DictEntry;
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<char, Node>,
+    terminal: Vec<usize>,
+}
+
+/// A character trie keyed on both the simplified and traditional headword
+/// of every indexed entry.
+///
+/// # Examples
+/// ```
+/// use cedict::trie::Index;
+///
+/// let entries = vec![
+///     cedict::parse_dict_entry("你好 你好 [ni3 hao3] /Hello!/").unwrap(),
+///     cedict::parse_dict_entry("你 你 [ni3] /you/").unwrap(),
+/// ];
+/// let index = Index::build(entries.into_iter());
+///
+/// assert_eq!(index.lookup_exact("你").count(), 1);
+/// assert_eq!(index.prefix_search("你").count(), 2);
+/// assert_eq!(index.longest_match("你好吗").unwrap().simplified(), "你好");
+/// ```
+pub struct Index<T> {
+    entries: Vec<DictEntry<T>>,
+    root: Node,
+}
+
+impl<T: AsRef<str>> Index<T> {
+    /// Builds an index from every entry in `entries`, inserting both the
+    /// simplified and traditional headword.
+    pub fn build(entries: impl Iterator<Item = DictEntry<T>>) -> Self {
+        let mut index = Index {
+            entries: Vec::new(),
+            root: Node::default(),
+        };
+
+        for entry in entries {
+            index.insert(entry);
+        }
+
+        index
+    }
+
+    fn insert(&mut self, entry: DictEntry<T>) {
+        let id = self.entries.len();
+        self.entries.push(entry);
+        let entry = &self.entries[id];
+
+        Self::insert_headword(&mut self.root, entry.simplified(), id);
+        if entry.traditional() != entry.simplified() {
+            Self::insert_headword(&mut self.root, entry.traditional(), id);
+        }
+    }
+
+    fn insert_headword(root: &mut Node, headword: &str, id: usize) {
+        let mut node = root;
+        for c in headword.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.terminal.push(id);
+    }
+
+    fn walk<'a>(node: &'a Node, word: &str) -> Option<&'a Node> {
+        let mut node = node;
+        for c in word.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+
+    fn collect_terminals(node: &Node, out: &mut Vec<usize>) {
+        out.extend_from_slice(&node.terminal);
+        for child in node.children.values() {
+            Self::collect_terminals(child, out);
+        }
+    }
+
+    /// Returns every entry whose simplified or traditional headword is
+    /// exactly `word`.
+    pub fn lookup_exact<'a>(&'a self, word: &str) -> impl Iterator<Item = &'a DictEntry<T>> {
+        let terminal: &[usize] = match Self::walk(&self.root, word) {
+            Some(node) => &node.terminal,
+            None => &[],
+        };
+
+        terminal.iter().map(move |&id| &self.entries[id])
+    }
+
+    /// Returns every entry whose simplified or traditional headword starts
+    /// with `prefix`.
+    pub fn prefix_search<'a>(&'a self, prefix: &str) -> impl Iterator<Item = &'a DictEntry<T>> {
+        let mut ids = Vec::new();
+        if let Some(node) = Self::walk(&self.root, prefix) {
+            Self::collect_terminals(node, &mut ids);
+        }
+        // An entry whose simplified and traditional headwords both start
+        // with `prefix` is reached twice; report it once.
+        ids.sort_unstable();
+        ids.dedup();
+
+        ids.into_iter().map(move |id| &self.entries[id])
+    }
+
+    /// Greedily matches the longest headword that is a prefix of `text`,
+    /// the building block for segmenting running Chinese text.
+    pub fn longest_match(&self, text: &str) -> Option<&DictEntry<T>> {
+        let mut node = &self.root;
+        let mut best = None;
+
+        for c in text.chars() {
+            node = match node.children.get(&c) {
+                Some(next) => next,
+                None => break,
+            };
+            if let Some(&id) = node.terminal.first() {
+                best = Some(id);
+            }
+        }
+
+        best.map(|id| &self.entries[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_index() -> Index<String> {
+        let entries = vec![
+            crate::parse_dict_entry("你好 你好 [ni3 hao3] /Hello!/Hi!/".to_string()).unwrap(),
+            crate::parse_dict_entry("你 你 [ni3] /you/".to_string()).unwrap(),
+            crate::parse_dict_entry("好 好 [hao3] /good/".to_string()).unwrap(),
+        ];
+        Index::build(entries.into_iter())
+    }
+
+    #[test]
+    fn test_lookup_exact() {
+        let index = build_index();
+        let matches: Vec<_> = index.lookup_exact("你好").collect();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].simplified(), "你好");
+
+        assert_eq!(index.lookup_exact("不存在").count(), 0);
+    }
+
+    #[test]
+    fn test_prefix_search() {
+        let index = build_index();
+        let matches: Vec<_> = index.prefix_search("你").collect();
+        assert_eq!(matches.len(), 2);
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let index = build_index();
+        let entry = index.longest_match("你好吗").unwrap();
+        assert_eq!(entry.simplified(), "你好");
+    }
+}